@@ -0,0 +1,105 @@
+//! Maintains a per-file contribution cache so `--watch` only re-reads and
+//! re-parses the files a debounced event actually touched, instead of
+//! walking and re-parsing the entire input tree on every change.
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::collections::hash_map::DefaultHasher;
+
+use walkdir::WalkDir;
+
+use crate::codegen::FileContribution;
+use crate::error;
+use crate::parser;
+
+pub struct ContributionCache {
+    contributions: HashMap<PathBuf, FileContribution>,
+    checksums: HashMap<PathBuf, u64>,
+}
+
+impl ContributionCache {
+    /// Walks `input` once, parsing every `.ts` file and populating both the
+    /// contribution and checksum maps from scratch.
+    pub fn scan(input: &Path) -> error::Result<Self> {
+        let mut cache = ContributionCache {
+            contributions: HashMap::new(),
+            checksums: HashMap::new(),
+        };
+
+        for entry in WalkDir::new(input) {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    eprintln!("{}", error::Error::ReadDir(e));
+                    continue;
+                }
+            };
+            let path = entry.path();
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("ts") {
+                cache.refresh(path)?;
+            }
+        }
+
+        Ok(cache)
+    }
+
+    /// Re-reads and re-parses `path`, skipping the work entirely if its
+    /// content checksum hasn't changed. Returns whether the cache changed.
+    pub fn refresh(&mut self, path: &Path) -> error::Result<bool> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(self.remove(path));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let checksum = checksum(&contents);
+        if self.checksums.get(path) == Some(&checksum) {
+            return Ok(false);
+        }
+
+        let parsed = parser::parse_file(&contents).map_err(|e| error::Error::Parse {
+            path: path.to_path_buf(),
+            detail: e.to_string(),
+        })?;
+
+        self.checksums.insert(path.to_path_buf(), checksum);
+        match parsed {
+            Some(parsed) => {
+                self.contributions.insert(
+                    path.to_path_buf(),
+                    FileContribution {
+                        group_name: parsed.group_name,
+                        class_name: parsed.class_name,
+                        methods: parsed.methods,
+                    },
+                );
+            }
+            None => {
+                self.contributions.remove(path);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Drops a removed file's cached contribution and checksum. Returns
+    /// whether it was previously present.
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let had_contribution = self.contributions.remove(path).is_some();
+        let had_checksum = self.checksums.remove(path).is_some();
+        had_contribution || had_checksum
+    }
+
+    pub fn contributions(&self) -> &HashMap<PathBuf, FileContribution> {
+        &self.contributions
+    }
+}
+
+fn checksum(contents: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    hasher.finish()
+}
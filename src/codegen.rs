@@ -0,0 +1,116 @@
+//! Merges parsed per-file contributions into the `output.ts` endpoint map
+//! and renders the generated TypeScript. Kept separate from how those
+//! contributions were gathered (a full scan vs. an incrementally maintained
+//! cache) so both paths render identically.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use crate::error::{self, Error};
+use crate::parser::ParsedMethod;
+
+/// Everything a single `.ts` file contributes to the generated API surface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileContribution {
+    pub group_name: String,
+    pub class_name: String,
+    pub methods: Vec<ParsedMethod>,
+}
+
+/// A single generated endpoint binding, keyed by group name then method name.
+pub struct Endpoint {
+    pub param_defs: String,
+    pub param_names: String,
+    pub full_route_name: String,
+    pub return_type: Option<String>,
+}
+
+/// `BTreeMap` rather than `HashMap` so groups and methods render in a
+/// stable order: `HashMap`'s iteration order is randomized per instance, so
+/// `--check` would see spurious diffs and watch mode would rewrite
+/// `output.ts` on nearly every rebuild even when nothing actually changed.
+pub type Endpoints = BTreeMap<String, BTreeMap<String, Endpoint>>;
+
+/// Merges every cached file contribution into a single endpoint map,
+/// re-running duplicate-method detection across the whole set each time.
+pub fn merge(contributions: &HashMap<PathBuf, FileContribution>) -> error::Result<Endpoints> {
+    let mut endpoints: Endpoints = BTreeMap::new();
+    let mut method_sources: HashMap<(String, String), Vec<PathBuf>> = HashMap::new();
+
+    for (path, contribution) in contributions {
+        for method in &contribution.methods {
+            let param_defs = method
+                .params
+                .iter()
+                .map(|p| p.render_def())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let param_names = method
+                .params
+                .iter()
+                .map(|p| p.name.clone())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let full_route_name = format!("{}-{}", contribution.group_name, method.name);
+            let group_methods = endpoints
+                .entry(contribution.group_name.clone())
+                .or_insert_with(BTreeMap::new);
+
+            if group_methods.contains_key(&method.name) {
+                let mut sources = method_sources
+                    .get(&(contribution.group_name.clone(), method.name.clone()))
+                    .cloned()
+                    .unwrap_or_default();
+                sources.push(path.clone());
+                return Err(Error::DuplicateMethod {
+                    group: contribution.group_name.clone(),
+                    method: method.name.clone(),
+                    sources,
+                });
+            }
+
+            group_methods.insert(
+                method.name.clone(),
+                Endpoint {
+                    param_defs,
+                    param_names,
+                    full_route_name,
+                    return_type: method.return_type.clone(),
+                },
+            );
+            method_sources
+                .entry((contribution.group_name.clone(), method.name.clone()))
+                .or_insert_with(Vec::new)
+                .push(path.clone());
+        }
+    }
+
+    Ok(endpoints)
+}
+
+/// Renders a merged endpoint map into the contents of `output.ts`.
+pub fn render(endpoints: &Endpoints) -> String {
+    let mut buffer = String::new();
+    buffer.push_str("import { ipcRenderer } from \"electron\";\n\n");
+    buffer.push_str("export const api = {\n");
+
+    for (group_name, methods) in endpoints {
+        buffer.push_str(&format!("  {}: {{\n", group_name));
+        for (method_name, endpoint) in methods {
+            let return_type = endpoint.return_type.as_deref().unwrap_or("any");
+            buffer.push_str(&format!(
+                "    {}: async ({}): Promise<{}> => {{\n",
+                method_name, endpoint.param_defs, return_type
+            ));
+            buffer.push_str(&format!(
+                "      return await ipcRenderer.invoke(\"{}\", {});\n",
+                endpoint.full_route_name, endpoint.param_names
+            ));
+            buffer.push_str("    },\n");
+        }
+        buffer.push_str("  },\n");
+    }
+    buffer.push_str("};\n");
+
+    buffer
+}
@@ -0,0 +1,80 @@
+//! Structured errors for the generator, so callers can match on failure kind
+//! and every failure keeps the file(s) that caused it attached.
+
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The same method name was declared more than once in the same `@backendAPI` group.
+    DuplicateMethod {
+        group: String,
+        method: String,
+        sources: Vec<PathBuf>,
+    },
+    /// A filesystem read/write failed.
+    Io(std::io::Error),
+    /// Walking the input directory failed.
+    ReadDir(walkdir::Error),
+    /// A `.ts` file could not be parsed into a class/method structure.
+    Parse { path: PathBuf, detail: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::DuplicateMethod {
+                group,
+                method,
+                sources,
+            } => {
+                writeln!(
+                    f,
+                    "Duplicate method name '{}' found in group '{}':",
+                    method, group
+                )?;
+                for (i, source) in sources.iter().enumerate() {
+                    if i + 1 == sources.len() {
+                        write!(f, "- {}", source.display())?;
+                    } else {
+                        writeln!(f, "- {}", source.display())?;
+                    }
+                }
+                Ok(())
+            }
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::ReadDir(e) => write!(f, "Error reading directory entry: {}", e),
+            Error::Parse { path, detail } => {
+                write!(f, "Failed to parse {}: {}", path.display(), detail)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<walkdir::Error> for Error {
+    fn from(e: walkdir::Error) -> Self {
+        Error::ReadDir(e)
+    }
+}
+
+impl Error {
+    /// The process exit code this error should produce.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::DuplicateMethod { .. } => 2,
+            Error::Parse { .. } => 3,
+            Error::ReadDir(_) => 4,
+            Error::Io(_) => 1,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -1,15 +1,20 @@
+mod cache;
+mod codegen;
+mod error;
+mod parser;
+
 use std::fs::File;
 use std::io::{self, Write};
-use regex::Regex;
-use walkdir::WalkDir;
 use std::path::PathBuf;
-use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use notify_debouncer_full::new_debouncer;
-use notify_debouncer_full::notify::{RecursiveMode, Watcher};
+use notify_debouncer_full::notify::{EventKind, RecursiveMode, Watcher};
+use notify_debouncer_full::DebouncedEvent;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 use clap::Parser;
 
+use cache::ContributionCache;
+
 #[derive(Parser, Debug)]
 #[command(name = "ts_endpoint_generator")]
 struct Opt {
@@ -22,167 +27,266 @@ struct Opt {
     // Watch for file changes
     #[arg(long)]
     watch: bool,
+    // Verify the output file is up to date instead of writing it
+    #[arg(long)]
+    check: bool,
+    // Clear the terminal before each watch rebuild
+    #[arg(long)]
+    clear: bool,
 }
 
-fn main() -> io::Result<()> {
-
+fn main() {
     let opt = Opt::parse();
 
     let mut stdout = StandardStream::stdout(ColorChoice::Auto);
 
     if opt.watch {
-        use std::sync::mpsc::channel;
-
-        let (tx, rx) = channel();
-        let mut debouncer = new_debouncer(Duration::from_secs(1), None, tx).unwrap();
-        debouncer.watcher().watch(&opt.input, RecursiveMode::Recursive).unwrap();
-
-        let mut color_spec = ColorSpec::new();
-        color_spec.set_bold(true).set_fg(Some(Color::Yellow));
-        stdout.set_color(&color_spec)?;
-        write!(&mut stdout, "!")?;
-        stdout.reset()?;
-        writeln!(
-            &mut stdout,
-            " Watching for changes in {:?}...",
-            &opt.input
-        )?;
-
-        if let Err(e) = process_files(&opt) {
-            print_error(&mut stdout, &format!("Error during initial processing: {:?}", e))?;
+        run_watch(&opt, &mut stdout);
+    } else if let Err(e) = process_files(&opt) {
+        print_error(&mut stdout, &format!("{}", e)).unwrap();
+        std::process::exit(e.exit_code());
+    }
+}
+
+fn run_watch(opt: &Opt, stdout: &mut StandardStream) {
+    use std::sync::mpsc::channel;
+
+    let (tx, rx) = channel();
+    let mut debouncer = new_debouncer(Duration::from_secs(1), None, tx).unwrap();
+    debouncer.watcher().watch(&opt.input, RecursiveMode::Recursive).unwrap();
+
+    let mut color_spec = ColorSpec::new();
+    color_spec.set_bold(true).set_fg(Some(Color::Yellow));
+    stdout.set_color(&color_spec).unwrap();
+    write!(stdout, "!").unwrap();
+    stdout.reset().unwrap();
+    writeln!(stdout, " Watching for changes in {:?}...", &opt.input).unwrap();
+
+    let mut cache = match ContributionCache::scan(&opt.input) {
+        Ok(cache) => cache,
+        Err(e) => {
+            print_error(stdout, &format!("Error during initial processing: {}", e)).unwrap();
+            std::process::exit(1);
         }
+    };
+    if let Err(e) = render_and_write(opt, &cache, stdout) {
+        print_error(stdout, &format!("Error during initial processing: {}", e)).unwrap();
+    }
 
-        for result in rx {
-            match result {
-                Ok(_events) => {
-                    let mut color_spec = ColorSpec::new();
-                    color_spec.set_bold(true).set_fg(Some(Color::Yellow));
-                    stdout.set_color(&color_spec)?;
-                    writeln!(&mut stdout, "! Detected changes")?;
-                    stdout.reset()?;
-                    if let Err(e) = process_files(&opt) {
-                        print_error(&mut stdout, &format!("{:?}", e))?;
-                    }
+    for result in rx {
+        match result {
+            Ok(events) => {
+                if opt.clear {
+                    clearscreen::clear().unwrap();
                 }
-                Err(errors) => {
-                    for error in errors {
-                        print_error(&mut stdout, &format!("{:?}", error))?;
+                report_changes(stdout, &events).unwrap();
+
+                for event in &events {
+                    for path in &event.paths {
+                        if path.extension().and_then(|s| s.to_str()) != Some("ts") {
+                            continue;
+                        }
+                        let result = if matches!(event.kind, EventKind::Remove(_)) {
+                            Ok(cache.remove(path))
+                        } else {
+                            cache.refresh(path)
+                        };
+                        if let Err(e) = result {
+                            print_error(stdout, &format!("{}", e)).unwrap();
+                        }
                     }
                 }
+
+                if let Err(e) = render_and_write(opt, &cache, stdout) {
+                    print_error(stdout, &format!("{}", e)).unwrap();
+                }
+            }
+            Err(errors) => {
+                for error in errors {
+                    print_error(stdout, &format!("{}", error)).unwrap();
+                }
             }
         }
-    } else {
-        if let Err(e) = process_files(&opt) {
-            print_error(&mut stdout, &format!("{:?}", e))?;
+    }
+}
+
+/// Merges the cache's current contributions, and rewrites `opt.output` only
+/// if the rendered result actually changed.
+fn render_and_write(
+    opt: &Opt,
+    cache: &ContributionCache,
+    stdout: &mut StandardStream,
+) -> error::Result<()> {
+    let start_time = Instant::now();
+    let endpoints = codegen::merge(cache.contributions())?;
+    let buffer = codegen::render(&endpoints);
+
+    let existing = std::fs::read_to_string(&opt.output).unwrap_or_default();
+    if existing != buffer {
+        let mut file = File::create(&opt.output)?;
+        file.write_all(buffer.as_bytes())?;
+        report_duration(stdout, "Finished", start_time.elapsed())?;
+    }
+
+    Ok(())
+}
+
+fn process_files(opt: &Opt) -> error::Result<()> {
+    let start_time = Instant::now();
+    let buffer = generate_output(opt)?;
+
+    if opt.check {
+        let existing = std::fs::read_to_string(&opt.output).unwrap_or_default();
+        if existing != buffer {
+            let mut stdout = StandardStream::stdout(ColorChoice::Auto);
+            print_diff(&mut stdout, &opt.output, &existing, &buffer)?;
             std::process::exit(1);
         }
+    } else {
+        let mut file = File::create(&opt.output)?;
+        file.write_all(buffer.as_bytes())?;
     }
 
+    let mut stdout = StandardStream::stdout(ColorChoice::Auto);
+    let verb = if opt.check { "Verified" } else { "Finished" };
+    report_duration(&mut stdout, verb, start_time.elapsed())?;
+
     Ok(())
 }
 
-fn process_files(opt: &Opt) -> io::Result<()> {
-    let start_time = Instant::now();
-    let backend_api_re = Regex::new(r#"@backendAPI\(\s*"(?P<group_name>[^"]+)"\s*\)"#).unwrap();
-    let class_re = Regex::new(r"class\s+(?P<class_name>\w+)\s*").unwrap();
-    let method_re = Regex::new(r#"@route\(\s*\)\s+async\s+(?P<method_name>\w+)\s*\((?P<params>[^)]*)\)"#).unwrap();
-    let mut endpoints: HashMap<String, HashMap<String, (String, String, String)>> = HashMap::new();
-    let mut method_sources: HashMap<(String, String), Vec<String>> = HashMap::new();
-
-    for entry in WalkDir::new(&opt.input) {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(e) => {
-                eprintln!("Error reading directory entry: {}", e);
-                continue;
-            }
-        };
-        let path = entry.path();
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("ts") {
-            let contents = std::fs::read_to_string(path)?;
-
-            let backend_api_cap = backend_api_re.captures(&contents);
-            let group_name = if let Some(cap) = backend_api_cap {
-                cap["group_name"].to_string()
-            } else {
-                continue;
-            };
-
-            let class_cap = class_re.captures(&contents);
-            let class_name = if let Some(cap) = class_cap {
-                cap["class_name"].to_string()
-            } else {
-                continue;
-            };
-
-            for cap in method_re.captures_iter(&contents) {
-                let method_name = cap["method_name"].to_string();
-                let params = &cap["params"];
-                let params_list: Vec<&str> = params.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
-                let mut param_names = Vec::new();
-                let mut param_defs = Vec::new();
-
-                for param in params_list {
-                    let parts: Vec<&str> = param.split(':').map(|s| s.trim()).collect();
-                    if parts.len() == 2 {
-                        param_names.push(parts[0].to_string());
-                        param_defs.push(format!("{}: {}", parts[0], parts[1]));
-                    }
-                }
+/// Walks `opt.input`, parses every `.ts` file, and renders the generated
+/// `output.ts` contents into a `String` without writing anything to disk.
+fn generate_output(opt: &Opt) -> error::Result<String> {
+    let cache = ContributionCache::scan(&opt.input)?;
+    let endpoints = codegen::merge(cache.contributions())?;
+    Ok(codegen::render(&endpoints))
+}
 
-                let param_defs_str = param_defs.join(", ");
-                let param_names_str = param_names.join(", ");
-                let full_route_name = format!("{}-{}", group_name, method_name);
-                let group_methods = endpoints.entry(group_name.clone()).or_insert_with(HashMap::new);
+/// Prints the watch rebuild banner, grouping the triggering events by
+/// created/modified/removed so the user can see what actually changed.
+fn report_changes(stdout: &mut StandardStream, events: &[DebouncedEvent]) -> io::Result<()> {
+    let mut color_spec = ColorSpec::new();
+    color_spec.set_bold(true).set_fg(Some(Color::Yellow));
+    stdout.set_color(&color_spec)?;
+    writeln!(stdout, "! Detected changes")?;
+    stdout.reset()?;
 
-                if group_methods.contains_key(&method_name) {
-                    let sources = method_sources.get(&(group_name.clone(), method_name.clone())).unwrap();
-                    let mut error_message = format!("Duplicate method name '{}' found in group '{}':\n", method_name, group_name);
-                    for source in sources {
-                        error_message.push_str(&format!("- {}\n", source));
-                    }
-                    error_message.push_str(&format!("- {}", class_name));
-                    return Err(io::Error::new(io::ErrorKind::Other, error_message));
-                } else {
-                    group_methods.insert(method_name.clone(), (param_defs_str, param_names_str, full_route_name));
-                    method_sources.entry((group_name.clone(), method_name.clone())).or_insert_with(Vec::new).push(class_name.clone());
-                }
-            }
-        }
+    let mut created = Vec::new();
+    let mut modified = Vec::new();
+    let mut removed = Vec::new();
+    let mut other = Vec::new();
+
+    for event in events {
+        let group = match event.kind {
+            EventKind::Create(_) => &mut created,
+            EventKind::Modify(_) => &mut modified,
+            EventKind::Remove(_) => &mut removed,
+            _ => &mut other,
+        };
+        group.extend(event.paths.iter());
     }
 
-    let mut file = File::create(&opt.output)?;
-    writeln!(file, "import {{ ipcRenderer }} from \"electron\";\n")?;
-    writeln!(file, "export const api = {{")?;
+    print_path_group(stdout, "created", Color::Green, &created)?;
+    print_path_group(stdout, "modified", Color::Yellow, &modified)?;
+    print_path_group(stdout, "removed", Color::Red, &removed)?;
+    print_path_group(stdout, "changed", Color::Cyan, &other)?;
 
-    for (group_name, methods) in endpoints {
-        writeln!(file, "  {}: {{", group_name)?;
-        for (method_name, (param_defs_str, param_names_str, full_route_name)) in methods {
-            writeln!(file, "    {}: async ({}) => {{", method_name, param_defs_str)?;
-            writeln!(file, "      return await ipcRenderer.invoke(\"{}\", {});", full_route_name, param_names_str)?;
-            writeln!(file, "    }},")?;
-        }
-        writeln!(file, "  }},")?;
+    Ok(())
+}
+
+fn print_path_group(
+    stdout: &mut StandardStream,
+    label: &str,
+    color: Color,
+    paths: &[&PathBuf],
+) -> io::Result<()> {
+    if paths.is_empty() {
+        return Ok(());
     }
-    writeln!(file, "}};")?;
 
-    let duration = start_time.elapsed();
-    let mut stdout = StandardStream::stdout(ColorChoice::Auto);
+    let mut color_spec = ColorSpec::new();
+    color_spec.set_fg(Some(color));
+    stdout.set_color(&color_spec)?;
+    write!(stdout, "  {}: ", label)?;
+    stdout.reset()?;
+    writeln!(
+        stdout,
+        "{}",
+        paths
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    )?;
+
+    Ok(())
+}
 
+fn report_duration(stdout: &mut StandardStream, verb: &str, duration: Duration) -> io::Result<()> {
+    let mut color_spec = ColorSpec::new();
+    color_spec.set_bold(true).set_fg(Some(Color::Green));
+    stdout.set_color(&color_spec)?;
     if duration.as_secs() >= 1 {
-        let mut color_spec = ColorSpec::new();
-        color_spec.set_bold(true).set_fg(Some(Color::Green));
-        stdout.set_color(&color_spec)?;
-        writeln!(&mut stdout, "✓ Finished in {} seconds.", duration.as_secs())?;
-        stdout.reset()?;
+        writeln!(stdout, "✓ {} in {} seconds.", verb, duration.as_secs())?;
     } else {
-        let mut color_spec = ColorSpec::new();
-        color_spec.set_bold(true).set_fg(Some(Color::Green));
-        stdout.set_color(&color_spec)?;
-        writeln!(&mut stdout, "✓ Finished in {} ms.", duration.as_millis())?;
-        stdout.reset()?;
+        writeln!(stdout, "✓ {} in {} ms.", verb, duration.as_millis())?;
+    }
+    stdout.reset()?;
+    Ok(())
+}
+
+/// Prints a unified-style diff between the committed `output.ts` (`old`) and
+/// the freshly generated contents (`new`), so `--check` failures in CI show
+/// exactly what is stale.
+fn print_diff(stdout: &mut StandardStream, path: &PathBuf, old: &str, new: &str) -> io::Result<()> {
+    let mut color_spec = ColorSpec::new();
+    color_spec.set_bold(true).set_fg(Some(Color::Red));
+    stdout.set_color(&color_spec)?;
+    writeln!(stdout, "❌ {:?} is out of date:", path)?;
+    stdout.reset()?;
+
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let mut prefix = 0;
+    while prefix < old_lines.len()
+        && prefix < new_lines.len()
+        && old_lines[prefix] == new_lines[prefix]
+    {
+        prefix += 1;
+    }
+
+    let mut suffix = 0;
+    while suffix < old_lines.len() - prefix
+        && suffix < new_lines.len() - prefix
+        && old_lines[old_lines.len() - 1 - suffix] == new_lines[new_lines.len() - 1 - suffix]
+    {
+        suffix += 1;
     }
 
+    writeln!(
+        stdout,
+        "@@ -{},{} +{},{} @@",
+        prefix + 1,
+        old_lines.len() - prefix - suffix,
+        prefix + 1,
+        new_lines.len() - prefix - suffix
+    )?;
+
+    for line in &old_lines[prefix..old_lines.len() - suffix] {
+        let mut spec = ColorSpec::new();
+        spec.set_fg(Some(Color::Red));
+        stdout.set_color(&spec)?;
+        writeln!(stdout, "-{}", line)?;
+    }
+    for line in &new_lines[prefix..new_lines.len() - suffix] {
+        let mut spec = ColorSpec::new();
+        spec.set_fg(Some(Color::Green));
+        stdout.set_color(&spec)?;
+        writeln!(stdout, "+{}", line)?;
+    }
+    stdout.reset()?;
+
     Ok(())
 }
 
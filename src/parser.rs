@@ -0,0 +1,532 @@
+//! A small hand-rolled TypeScript parser used to pull `@backendAPI` classes
+//! and their `@route` methods out of a source file.
+//!
+//! This intentionally does not use regexes: method signatures can span
+//! multiple lines, contain generics with commas (`Map<string, number>`),
+//! destructured parameters, and default values, none of which a line-oriented
+//! regex can track reliably. Instead we tokenize the file (skipping comments
+//! and string contents so decorators inside them are never mistaken for real
+//! ones) and walk the resulting token stream the way a real parser would.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    /// A single punctuation character we care about, kept as a char so callers
+    /// can match on it directly (`(`, `)`, `{`, `}`, `<`, `>`, `,`, `:`, `=`, `@`, `;`,
+    /// `[`, `]`, `.`, `?`, `|`, `&`).
+    Punct(char),
+    /// The arrow in an arrow-function type, e.g. `(x: number) => void`.
+    /// Tokenized as a single unit rather than `=` followed by `>` so its `>`
+    /// is never mistaken for a generic's closing bracket (which would throw
+    /// off `<`/`>` depth tracking in `split_top_level`/`parse_param`) and its
+    /// `=` is never mistaken for a default-value marker.
+    Arrow,
+    /// A string literal, stored without its surrounding quotes.
+    Str(String),
+    /// Anything else (numbers, other operators) we don't need to inspect.
+    Other(String),
+}
+
+/// A parsed method parameter, e.g. `userId: string` or `{ id, name }: User = DEFAULT_USER`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    /// The parameter's binding as written (a plain identifier or a destructuring pattern).
+    pub name: String,
+    /// The full type node, including generics, e.g. `Map<string, number>`.
+    pub type_node: Option<String>,
+    /// The default value expression, if any.
+    pub default: Option<String>,
+}
+
+impl Param {
+    /// Renders the parameter the way it should appear in a generated function signature.
+    pub fn render_def(&self) -> String {
+        let mut out = self.name.clone();
+        if let Some(ty) = &self.type_node {
+            out.push_str(": ");
+            out.push_str(ty);
+        }
+        if let Some(default) = &self.default {
+            out.push_str(" = ");
+            out.push_str(default);
+        }
+        out
+    }
+}
+
+/// A parsed `@route()` method found inside a `@backendAPI` class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedMethod {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub return_type: Option<String>,
+}
+
+/// A parsed class annotated with `@backendAPI("group")`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedClass {
+    pub group_name: String,
+    pub class_name: String,
+    pub methods: Vec<ParsedMethod>,
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub detail: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.detail)
+    }
+}
+
+/// Tokenizes `src`, stripping line/block comments and reducing string
+/// literals to their contents so nothing inside a comment or string can be
+/// mistaken for a decorator, class, or method.
+fn tokenize(src: &str) -> Vec<Token> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let is_ident_start = |c: char| c.is_alphabetic() || c == '_' || c == '$';
+    let is_ident_continue = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        // Line comment.
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        // Block comment.
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+
+        // String / template literals.
+        if c == '"' || c == '\'' || c == '`' {
+            let quote = c;
+            let mut value = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != quote {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    value.push(chars[i + 1]);
+                    i += 2;
+                } else {
+                    value.push(chars[i]);
+                    i += 1;
+                }
+            }
+            i += 1; // closing quote
+            tokens.push(Token::Str(value));
+            continue;
+        }
+
+        if is_ident_start(c) {
+            let start = i;
+            while i < chars.len() && is_ident_continue(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            continue;
+        }
+
+        // Numeric literal, kept as a single token so a multi-digit default
+        // value like `18` doesn't get a space inserted between its digits.
+        if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push(Token::Other(chars[start..i].iter().collect()));
+            continue;
+        }
+
+        if c == '=' && chars.get(i + 1) == Some(&'>') {
+            tokens.push(Token::Arrow);
+            i += 2;
+            continue;
+        }
+
+        match c {
+            '(' | ')' | '{' | '}' | '<' | '>' | ',' | ':' | '=' | '@' | ';' | '[' | ']' | '.'
+            | '?' | '|' | '&' => {
+                tokens.push(Token::Punct(c));
+                i += 1;
+            }
+            _ => {
+                tokens.push(Token::Other(c.to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Consumes a balanced run of tokens starting at an opening punctuation
+/// (`(`, `<`, `{`, or `[`) and returns its rendered source text (without the
+/// enclosing delimiters) along with the index just past the matching closer.
+fn collect_balanced(tokens: &[Token], open_idx: usize, open: char, close: char) -> (String, usize) {
+    let mut depth = 1;
+    let mut j = open_idx + 1;
+    let mut inner = Vec::new();
+    while j < tokens.len() && depth > 0 {
+        match &tokens[j] {
+            Token::Punct(c) if *c == open => {
+                depth += 1;
+                inner.push(tokens[j].clone());
+            }
+            Token::Punct(c) if *c == close => {
+                depth -= 1;
+                if depth > 0 {
+                    inner.push(tokens[j].clone());
+                }
+            }
+            tok => inner.push(tok.clone()),
+        }
+        j += 1;
+    }
+    (render_tokens(&inner), j)
+}
+
+/// Renders a token sequence back into source text, using the *pair* of
+/// tokens on either side of each boundary to decide whether a space belongs
+/// there (rather than guessing from the rendered string's trailing
+/// character). This is what keeps `Map<string, number>` free of the spaces
+/// around `<`/`>` that a naive "space between every token" renderer would
+/// introduce, while still spacing `param = default` and `a | b` correctly.
+fn render_tokens(tokens: &[Token]) -> String {
+    let mut out = String::new();
+    let mut prev: Option<&Token> = None;
+    for tok in tokens {
+        if let Some(prev) = prev {
+            if needs_space(prev, tok) {
+                out.push(' ');
+            }
+        }
+        match tok {
+            Token::Ident(s) => out.push_str(s),
+            Token::Punct(c) => out.push(*c),
+            Token::Arrow => out.push_str("=>"),
+            Token::Str(s) => {
+                out.push('"');
+                out.push_str(s);
+                out.push('"');
+            }
+            Token::Other(s) => out.push_str(s),
+        }
+        prev = Some(tok);
+    }
+    out
+}
+
+/// Whether a space belongs between two adjacent tokens when rendering them
+/// back into source text.
+fn needs_space(prev: &Token, curr: &Token) -> bool {
+    match (prev, curr) {
+        // Nothing hugs the inside of an opening bracket/generic.
+        (Token::Punct('(') | Token::Punct('[') | Token::Punct('<'), _) => false,
+        // Nothing precedes a closing bracket/generic, comma, colon, dot, `?`, or `;`.
+        (
+            _,
+            Token::Punct(')')
+            | Token::Punct(']')
+            | Token::Punct('>')
+            | Token::Punct(',')
+            | Token::Punct(':')
+            | Token::Punct('.')
+            | Token::Punct('?')
+            | Token::Punct(';'),
+        ) => false,
+        // A generic/call/array type's opening `(`/`<`/`[` hugs the identifier
+        // (or closing bracket) before it: `Map<`, `foo(`, `string[`, `T[][]`.
+        (
+            Token::Ident(_) | Token::Punct(')') | Token::Punct(']') | Token::Punct('>'),
+            Token::Punct('(') | Token::Punct('<') | Token::Punct('['),
+        ) => false,
+        // `a.b` has no space around the dot.
+        (Token::Punct('.'), _) => false,
+        // Everything after a comma or a colon gets a space: `a, b`, `id: string`.
+        (Token::Punct(',') | Token::Punct(':'), _) => true,
+        _ => true,
+    }
+}
+
+/// Splits the inside of a parameter list on top-level commas, i.e. commas
+/// that are not nested inside `<>`, `()`, `[]`, or `{}` (so generics like
+/// `Map<string, number>` stay intact).
+fn split_top_level(tokens: &[Token]) -> Vec<Vec<Token>> {
+    let mut groups = Vec::new();
+    let mut current = Vec::new();
+    let mut depth = 0i32;
+    for tok in tokens {
+        match tok {
+            Token::Punct('<') | Token::Punct('(') | Token::Punct('[') | Token::Punct('{') => {
+                depth += 1;
+                current.push(tok.clone());
+            }
+            Token::Punct('>') | Token::Punct(')') | Token::Punct(']') | Token::Punct('}') => {
+                depth -= 1;
+                current.push(tok.clone());
+            }
+            Token::Punct(',') if depth == 0 => {
+                groups.push(std::mem::take(&mut current));
+            }
+            _ => current.push(tok.clone()),
+        }
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+/// An async method's declared return type is always `Promise<T>` — that's
+/// the only legal annotation TypeScript allows there. We store the inner
+/// `T` so callers can wrap it in `Promise<...>` themselves without doubling
+/// up; a declared type that isn't wrapped in `Promise<...>` is kept as-is.
+fn unwrap_promise(return_type: &str) -> String {
+    return_type
+        .strip_prefix("Promise<")
+        .and_then(|rest| rest.strip_suffix('>'))
+        .unwrap_or(return_type)
+        .to_string()
+}
+
+/// Parses a single parameter's tokens into its binding, type node, and default.
+fn parse_param(tokens: &[Token]) -> Option<Param> {
+    if tokens.is_empty() {
+        return None;
+    }
+
+    // Split off a top-level `= default` first, then a top-level `: Type`.
+    let mut depth = 0i32;
+    let mut eq_idx = None;
+    let mut colon_idx = None;
+    for (idx, tok) in tokens.iter().enumerate() {
+        match tok {
+            Token::Punct('<') | Token::Punct('(') | Token::Punct('[') | Token::Punct('{') => depth += 1,
+            Token::Punct('>') | Token::Punct(')') | Token::Punct(']') | Token::Punct('}') => depth -= 1,
+            Token::Punct('=') if depth == 0 && eq_idx.is_none() => eq_idx = Some(idx),
+            Token::Punct(':') if depth == 0 && colon_idx.is_none() && eq_idx.is_none() => {
+                colon_idx = Some(idx)
+            }
+            _ => {}
+        }
+    }
+
+    let (binding_and_type, default) = match eq_idx {
+        Some(idx) => (&tokens[..idx], Some(render_tokens(&tokens[idx + 1..]))),
+        None => (tokens, None),
+    };
+
+    let colon_idx = colon_idx.filter(|&idx| idx < binding_and_type.len());
+    let (name_tokens, type_tokens) = match colon_idx {
+        Some(idx) => (&binding_and_type[..idx], Some(&binding_and_type[idx + 1..])),
+        None => (binding_and_type, None),
+    };
+
+    let name = render_tokens(name_tokens);
+    if name.is_empty() {
+        return None;
+    }
+
+    let type_node = type_tokens.map(render_tokens);
+
+    Some(Param {
+        name,
+        type_node,
+        default,
+    })
+}
+
+/// Parses the source of a single `.ts` file and returns its `@backendAPI`
+/// class, if any. Files without a `@backendAPI`-annotated class are not
+/// considered part of the generated API surface, so `Ok(None)` is returned
+/// rather than an error.
+pub fn parse_file(contents: &str) -> Result<Option<ParsedClass>, ParseError> {
+    let tokens = tokenize(contents);
+    let mut i = 0;
+    let mut group_name = None;
+    let mut class_name = None;
+    let mut methods = Vec::new();
+
+    while i < tokens.len() {
+        // Decorator: `@` Ident `(` args `)`.
+        if let Token::Punct('@') = tokens[i] {
+            if let Some(Token::Ident(deco_name)) = tokens.get(i + 1) {
+                let deco_name = deco_name.clone();
+                let mut args = Vec::new();
+                let mut next = i + 2;
+                if matches!(tokens.get(next), Some(Token::Punct('('))) {
+                    let (rendered, after) = collect_balanced(&tokens, next, '(', ')');
+                    args.push(rendered);
+                    next = after;
+                }
+
+                if deco_name == "backendAPI" {
+                    if let Some(arg) = args.first() {
+                        group_name = Some(arg.trim_matches(|c| c == '"' || c == '\'').to_string());
+                    }
+                    i = next;
+                    continue;
+                }
+
+                if deco_name == "route" {
+                    if let Some(method) = parse_decorated_method(&tokens, next) {
+                        methods.push(method.0);
+                        i = method.1;
+                        continue;
+                    }
+                }
+
+                i = next;
+                continue;
+            }
+        }
+
+        if class_name.is_none() {
+            if let Token::Ident(kw) = &tokens[i] {
+                if kw == "class" {
+                    if let Some(Token::Ident(name)) = tokens.get(i + 1) {
+                        class_name = Some(name.clone());
+                    }
+                }
+            }
+        }
+
+        i += 1;
+    }
+
+    match (group_name, class_name) {
+        (Some(group_name), Some(class_name)) => Ok(Some(ParsedClass {
+            group_name,
+            class_name,
+            methods,
+        })),
+        (None, _) => Ok(None),
+        (Some(_), None) => Err(ParseError {
+            detail: "found @backendAPI decorator but no class declaration".to_string(),
+        }),
+    }
+}
+
+/// Starting just past a `@route(...)` decorator, expects `async <name>(<params>) [: <ReturnType>]`
+/// and returns the parsed method plus the index of the first token after its signature
+/// (normally the body's opening `{`, or a `;`).
+fn parse_decorated_method(tokens: &[Token], mut i: usize) -> Option<(ParsedMethod, usize)> {
+    if !matches!(tokens.get(i), Some(Token::Ident(kw)) if kw == "async") {
+        return None;
+    }
+    i += 1;
+
+    let name = match tokens.get(i) {
+        Some(Token::Ident(name)) => name.clone(),
+        _ => return None,
+    };
+    i += 1;
+
+    if !matches!(tokens.get(i), Some(Token::Punct('('))) {
+        return None;
+    }
+    let (params_src, after_params) = collect_balanced(tokens, i, '(', ')');
+    let param_tokens = tokenize(&params_src);
+    let params = split_top_level(&param_tokens)
+        .into_iter()
+        .filter_map(|group| parse_param(&group))
+        .collect();
+    i = after_params;
+
+    let mut return_type = None;
+    if matches!(tokens.get(i), Some(Token::Punct(':'))) {
+        i += 1;
+        let start = i;
+        while i < tokens.len() && !matches!(tokens[i], Token::Punct('{') | Token::Punct(';')) {
+            i += 1;
+        }
+        return_type = Some(unwrap_promise(&render_tokens(&tokens[start..i])));
+    }
+
+    Some((
+        ParsedMethod {
+            name,
+            params,
+            return_type,
+        },
+        i,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_generic_param_type_without_stray_spaces() {
+        let params = tokenize("counts: Map<string, number>");
+        let param = parse_param(&params).expect("param should parse");
+        assert_eq!(param.render_def(), "counts: Map<string, number>");
+    }
+
+    #[test]
+    fn splits_params_correctly_when_one_has_an_arrow_function_type() {
+        let class = parse_file(
+            r#"
+            @backendAPI("events")
+            class EventController {
+                @route()
+                async subscribe(a: (x: number) => void, b: string) {}
+            }
+            "#,
+        )
+        .unwrap()
+        .unwrap();
+
+        let method = &class.methods[0];
+        assert_eq!(method.params.len(), 2);
+        assert_eq!(method.params[0].render_def(), "a: (x: number) => void");
+        assert_eq!(method.params[1].render_def(), "b: string");
+    }
+
+    #[test]
+    fn renders_inline_object_type_without_a_stray_space_before_the_colon() {
+        let params = tokenize("opts: { id: string }");
+        let param = parse_param(&params).expect("param should parse");
+        assert_eq!(param.render_def(), "opts: { id: string }");
+    }
+
+    #[test]
+    fn unwraps_promise_return_type_so_it_isnt_double_wrapped() {
+        let class = parse_file(
+            r#"
+            @backendAPI("users")
+            class UserController {
+                @route()
+                async getUser(id: string): Promise<User> {}
+            }
+            "#,
+        )
+        .unwrap()
+        .unwrap();
+
+        let method = &class.methods[0];
+        assert_eq!(method.return_type.as_deref(), Some("User"));
+    }
+}